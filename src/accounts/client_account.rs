@@ -1,12 +1,33 @@
+use std::collections::HashMap;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
 
-#[derive(Debug)]
-pub struct ClientAccount {
-    pub client: u16,
+use crate::transactions::Currency;
+
+/// Available/held/total balance for a single asset.
+#[derive(Debug, Clone)]
+pub struct Balance {
     pub available: Decimal,
     pub held: Decimal,
     pub total: Decimal,
+}
+
+impl Balance {
+    fn new() -> Self {
+        Self {
+            available: Decimal::from_f64(0.0).unwrap(),
+            held: Decimal::from_f64(0.0).unwrap(),
+            total: Decimal::from_f64(0.0).unwrap(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClientAccount {
+    pub client: u16,
+    /// Per-asset balances. `locked` below is account-wide: a chargeback in
+    /// any asset freezes the whole client, not just that asset's balance.
+    pub balances: HashMap<Currency, Balance>,
     pub locked: bool,
 }
 
@@ -14,58 +35,70 @@ impl ClientAccount {
     pub fn new(client: u16) -> Self {
         Self {
             client,
-            available: Decimal::from_f64(0.0).unwrap(),
-            held: Decimal::from_f64(0.0).unwrap(),
-            total: Decimal::from_f64(0.0).unwrap(),
+            balances: HashMap::new(),
             locked: false,
         }
     }
 
-    pub fn deposit(&mut self, amount: Decimal) {
+    fn balance_mut(&mut self, currency: &str) -> &mut Balance {
+        self.balances
+            .entry(currency.to_string())
+            .or_insert_with(Balance::new)
+    }
+
+    pub fn deposit(&mut self, currency: &str, amount: Decimal) {
         if self.locked {
             return;
         }
-        self.available += amount;
-        self.total += amount;
+        let balance = self.balance_mut(currency);
+        balance.available += amount;
+        balance.total += amount;
     }
 
-    pub fn withdraw(&mut self, amount: Decimal) -> bool {
-        if self.locked || self.available < amount {
+    pub fn withdraw(&mut self, currency: &str, amount: Decimal) -> bool {
+        if self.locked {
             return false;
         }
-        self.available -= amount;
-        self.total -= amount;
+        let balance = self.balance_mut(currency);
+        if balance.available < amount {
+            return false;
+        }
+        balance.available -= amount;
+        balance.total -= amount;
         true
     }
 
-    pub fn hold(&mut self, amount: Decimal) {
+    pub fn hold(&mut self, currency: &str, amount: Decimal) {
         if self.locked {
             return;
         }
+        let balance = self.balance_mut(currency);
         // Only hold what is actually available
-        let hold_amount = amount.min(self.available);
-        self.available -= hold_amount;
-        self.held += hold_amount;
+        let hold_amount = amount.min(balance.available);
+        balance.available -= hold_amount;
+        balance.held += hold_amount;
     }
 
-    pub fn release(&mut self, amount: Decimal) {
+    pub fn release(&mut self, currency: &str, amount: Decimal) {
         if self.locked {
             return;
         }
+        let balance = self.balance_mut(currency);
         // Only release up to what is held
-        let release_amount = amount.min(self.held);
-        self.held -= release_amount;
-        self.available += release_amount;
+        let release_amount = amount.min(balance.held);
+        balance.held -= release_amount;
+        balance.available += release_amount;
     }
 
-    pub fn chargeback(&mut self, amount: Decimal) {
+    pub fn chargeback(&mut self, currency: &str, amount: Decimal) {
         if self.locked {
             return;
         }
+        let balance = self.balance_mut(currency);
         // Only chargeback up to what is held
-        let cb_amount = amount.min(self.held);
-        self.held -= cb_amount;
-        self.total -= cb_amount;
+        let cb_amount = amount.min(balance.held);
+        balance.held -= cb_amount;
+        balance.total -= cb_amount;
         self.locked = true;
     }
 
@@ -76,6 +109,7 @@ impl ClientAccount {
 mod tests {
     use super::*;
     use rust_decimal::prelude::FromPrimitive;
+    use crate::transactions::DEFAULT_CURRENCY;
 
     fn decimal(amount: f64) -> Decimal {
         Decimal::from_f64(amount).unwrap()
@@ -84,59 +118,77 @@ mod tests {
     #[test]
     fn test_deposit() {
         let mut acc = ClientAccount::new(1);
-        acc.deposit(decimal(100.0));
-        assert_eq!(acc.available, decimal(100.0));
-        assert_eq!(acc.held, decimal(0.0));
-        assert_eq!(acc.total, decimal(100.0));
+        acc.deposit(DEFAULT_CURRENCY, decimal(100.0));
+        let balance = &acc.balances[DEFAULT_CURRENCY];
+        assert_eq!(balance.available, decimal(100.0));
+        assert_eq!(balance.held, decimal(0.0));
+        assert_eq!(balance.total, decimal(100.0));
         assert!(!acc.locked);
     }
 
     #[test]
     fn test_withdraw() {
         let mut acc = ClientAccount::new(1);
-        acc.deposit(decimal(100.0));
-        acc.withdraw(decimal(40.0));
-        assert_eq!(acc.available, decimal(60.0));
-        assert_eq!(acc.total, decimal(60.0));
+        acc.deposit(DEFAULT_CURRENCY, decimal(100.0));
+        acc.withdraw(DEFAULT_CURRENCY, decimal(40.0));
+        let balance = &acc.balances[DEFAULT_CURRENCY];
+        assert_eq!(balance.available, decimal(60.0));
+        assert_eq!(balance.total, decimal(60.0));
 
         // Withdraw more than available → no change
-        acc.withdraw(decimal(100.0));
-        assert_eq!(acc.available, decimal(60.0));
-        assert_eq!(acc.total, decimal(60.0));
+        acc.withdraw(DEFAULT_CURRENCY, decimal(100.0));
+        let balance = &acc.balances[DEFAULT_CURRENCY];
+        assert_eq!(balance.available, decimal(60.0));
+        assert_eq!(balance.total, decimal(60.0));
     }
 
     #[test]
     fn test_hold_and_release() {
         let mut acc = ClientAccount::new(1);
-        acc.deposit(decimal(100.0));
+        acc.deposit(DEFAULT_CURRENCY, decimal(100.0));
 
         // Hold 70
-        acc.hold(decimal(70.0));
-        assert_eq!(acc.available, decimal(30.0));
-        assert_eq!(acc.held, decimal(70.0));
+        acc.hold(DEFAULT_CURRENCY, decimal(70.0));
+        let balance = &acc.balances[DEFAULT_CURRENCY];
+        assert_eq!(balance.available, decimal(30.0));
+        assert_eq!(balance.held, decimal(70.0));
 
         // Release 50
-        acc.release(decimal(50.0));
-        assert_eq!(acc.available, decimal(80.0));
-        assert_eq!(acc.held, decimal(20.0));
+        acc.release(DEFAULT_CURRENCY, decimal(50.0));
+        let balance = &acc.balances[DEFAULT_CURRENCY];
+        assert_eq!(balance.available, decimal(80.0));
+        assert_eq!(balance.held, decimal(20.0));
     }
 
     #[test]
     fn test_chargeback_locks_account() {
         let mut acc = ClientAccount::new(1);
-        acc.deposit(decimal(100.0));
-        acc.hold(decimal(50.0));
-        acc.chargeback(decimal(50.0));
-
-        assert_eq!(acc.available, decimal(50.0));
-        assert_eq!(acc.held, decimal(0.0));
-        assert_eq!(acc.total, decimal(50.0));
+        acc.deposit(DEFAULT_CURRENCY, decimal(100.0));
+        acc.hold(DEFAULT_CURRENCY, decimal(50.0));
+        acc.chargeback(DEFAULT_CURRENCY, decimal(50.0));
+
+        let balance = &acc.balances[DEFAULT_CURRENCY];
+        assert_eq!(balance.available, decimal(50.0));
+        assert_eq!(balance.held, decimal(0.0));
+        assert_eq!(balance.total, decimal(50.0));
         assert!(acc.locked);
 
         // Cannot deposit or withdraw after lock
-        acc.deposit(decimal(10.0));
-        acc.withdraw(decimal(10.0));
-        assert_eq!(acc.available, decimal(50.0));
-        assert_eq!(acc.total, decimal(50.0));
+        acc.deposit(DEFAULT_CURRENCY, decimal(10.0));
+        acc.withdraw(DEFAULT_CURRENCY, decimal(10.0));
+        let balance = &acc.balances[DEFAULT_CURRENCY];
+        assert_eq!(balance.available, decimal(50.0));
+        assert_eq!(balance.total, decimal(50.0));
+    }
+
+    #[test]
+    fn test_balances_are_tracked_per_currency() {
+        let mut acc = ClientAccount::new(1);
+        acc.deposit("BTC", decimal(2.0));
+        acc.deposit("ETH", decimal(10.0));
+        acc.withdraw("BTC", decimal(0.5));
+
+        assert_eq!(acc.balances["BTC"].available, decimal(1.5));
+        assert_eq!(acc.balances["ETH"].available, decimal(10.0));
     }
 }
@@ -1,16 +1,62 @@
 use std::collections::HashMap;
 use csv::Writer;
 use log::{debug, warn, info};
+use thiserror::Error;
 
 use crate::accounts::client_account::ClientAccount;
-use crate::transactions::{TransactionRecord, Transaction, TransactionType};
+use crate::transactions::{TransactionRecord, Transaction, TransactionType, TxState};
+
+/// Errors returned by [`PaymentEngine::try_process_transaction`].
+///
+/// `process_transaction` swallows all of these into a log line so existing
+/// callers keep seeing today's behavior; callers that want to react
+/// programmatically should call `try_process_transaction` directly.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LedgerError {
+    #[error("account for client {0} is locked")]
+    FrozenAccount(u16),
+    #[error("client {0} does not have enough available funds for tx {1}")]
+    NotEnoughFunds(u16, u32),
+    #[error("tx {1} for client {0} does not exist")]
+    UnknownTx(u16, u32),
+    #[error("tx {1} for client {0} is already disputed")]
+    AlreadyDisputed(u16, u32),
+    #[error("tx {1} for client {0} is not under dispute")]
+    NotDisputed(u16, u32),
+}
+
+/// Backing storage for account and transaction state.
+///
+/// `PaymentEngine` is generic over this trait so the `HashMap`-based
+/// [`MemStore`] below can later be swapped for a disk- or sled-backed
+/// implementation for transaction histories that exceed memory, without
+/// touching any processing logic in the engine itself.
+pub trait TransactionStore {
+    fn get_account(&self, client: u16) -> Option<&ClientAccount>;
+
+    /// Get a mutable reference to a client account, creating it if absent.
+    fn get_account_mut(&mut self, client: u16) -> &mut ClientAccount;
+
+    fn put_account(&mut self, account: ClientAccount);
+
+    fn get_tx(&self, client: u16, tx: u32) -> Option<&Transaction>;
+
+    fn put_tx(&mut self, client: u16, tx: u32, transaction: Transaction);
+
+    fn remove_tx(&mut self, client: u16, tx: u32) -> Option<Transaction>;
+
+    /// Iterate over every account currently in the store.
+    fn accounts(&self) -> Box<dyn Iterator<Item = &ClientAccount> + '_>;
+}
 
-pub struct PaymentEngine {
-    pub accounts: HashMap<u16, ClientAccount>,
-    pub transactions: HashMap<u32, Transaction>,
+/// Default in-memory [`TransactionStore`] backed by two `HashMap`s.
+#[derive(Default)]
+pub struct MemStore {
+    accounts: HashMap<u16, ClientAccount>,
+    transactions: HashMap<(u16, u32), Transaction>,
 }
 
-impl PaymentEngine {
+impl MemStore {
     pub fn new() -> Self {
         Self {
             accounts: HashMap::new(),
@@ -18,121 +64,218 @@ impl PaymentEngine {
         }
     }
 
+    /// Consume the store, handing back its accounts by value.
+    ///
+    /// Used to merge per-shard engines back together once parallel
+    /// processing finishes; see `orchestrator::run_parallel`.
+    pub(crate) fn into_accounts(self) -> HashMap<u16, ClientAccount> {
+        self.accounts
+    }
+}
+
+impl TransactionStore for MemStore {
+    fn get_account(&self, client: u16) -> Option<&ClientAccount> {
+        self.accounts.get(&client)
+    }
+
+    fn get_account_mut(&mut self, client: u16) -> &mut ClientAccount {
+        self.accounts
+            .entry(client)
+            .or_insert_with(|| ClientAccount::new(client))
+    }
+
+    fn put_account(&mut self, account: ClientAccount) {
+        self.accounts.insert(account.client, account);
+    }
+
+    fn get_tx(&self, client: u16, tx: u32) -> Option<&Transaction> {
+        self.transactions.get(&(client, tx))
+    }
+
+    fn put_tx(&mut self, client: u16, tx: u32, transaction: Transaction) {
+        self.transactions.insert((client, tx), transaction);
+    }
+
+    fn remove_tx(&mut self, client: u16, tx: u32) -> Option<Transaction> {
+        self.transactions.remove(&(client, tx))
+    }
+
+    fn accounts(&self) -> Box<dyn Iterator<Item = &ClientAccount> + '_> {
+        Box::new(self.accounts.values())
+    }
+}
+
+pub struct PaymentEngine<S: TransactionStore = MemStore> {
+    pub store: S,
+}
+
+impl PaymentEngine<MemStore> {
+    pub fn new() -> Self {
+        Self {
+            store: MemStore::new(),
+        }
+    }
+}
+
+impl<S: TransactionStore> PaymentEngine<S> {
+    /// Build an engine over a caller-supplied store, e.g. a non-default
+    /// `TransactionStore` implementation.
+    pub fn with_store(store: S) -> Self {
+        Self { store }
+    }
+
     /// Get mutable reference to a client account, or create new one if it doesn't exist
     fn get_account(&mut self, client_id: u16) -> &mut ClientAccount {
-        self.accounts
-            .entry(client_id)
-            .or_insert_with(|| ClientAccount::new(client_id))
+        self.store.get_account_mut(client_id)
     }
 
-    /// Process a single transaction
+    /// Process a single transaction, logging and discarding any error.
+    ///
+    /// This preserves the engine's original fire-and-forget behavior for
+    /// callers that don't need programmatic error handling. Prefer
+    /// [`Self::try_process_transaction`] to observe why a row was rejected.
     pub fn process_transaction(&mut self, record: TransactionRecord) {
+        let client = record.client;
+        let tx = record.tx;
+        if let Err(err) = self.try_process_transaction(record) {
+            warn!("tx {} for client {} rejected: {}", tx, client, err);
+        }
+    }
+
+    /// Process a single transaction, returning a typed [`LedgerError`] on rejection.
+    pub fn try_process_transaction(&mut self, record: TransactionRecord) -> Result<(), LedgerError> {
         match record.tx_type {
             TransactionType::Deposit => {
                 if let Some(amount) = record.amount {
+                    let currency = record.currency().to_string();
                     let account = self.get_account(record.client);
-                    account.deposit(amount);
+                    if account.locked {
+                        return Err(LedgerError::FrozenAccount(record.client));
+                    }
+                    account.deposit(&currency, amount);
 
-                    if self.transactions.contains_key(&record.tx) {
+                    if self.store.get_tx(record.client, record.tx).is_some() {
                         // Duplicate tx id: we've chosen to apply the new operation but log the overwrite
                         warn!("duplicate tx id {} for client {}: overwriting existing transaction", record.tx, record.client);
                     } else {
                         info!("recording deposit tx {} for client {} amount {}", record.tx, record.client, amount);
                     }
 
-                    self.transactions.insert(
+                    self.store.put_tx(
+                        record.client,
                         record.tx,
                         Transaction {
                             client: record.client,
                             amount,
-                            disputed: false,
+                            currency,
+                            state: TxState::Processed,
                         },
                     );
                 }
+                Ok(())
             }
             TransactionType::Withdrawal => {
                 if let Some(amount) = record.amount {
+                    let currency = record.currency().to_string();
                     let account = self.get_account(record.client);
+                    if account.locked {
+                        return Err(LedgerError::FrozenAccount(record.client));
+                    }
                     // Only record the transaction if the withdrawal actually succeeded
-                    if account.withdraw(amount) {
-                        self.transactions.insert(
+                    if account.withdraw(&currency, amount) {
+                        self.store.put_tx(
+                            record.client,
                             record.tx,
                             Transaction {
                                 client: record.client,
                                 amount,
-                                disputed: false,
+                                currency,
+                                state: TxState::Processed,
                             },
                         );
                     } else {
-                        // Log that a withdrawal failed and therefore was not recorded
-                        debug!("withdrawal failed or account locked for client {} tx {} amount {}", record.client, record.tx, amount);
+                        return Err(LedgerError::NotEnoughFunds(record.client, record.tx));
                     }
                 }
+                Ok(())
             }
             TransactionType::Dispute
             | TransactionType::Resolve
             | TransactionType::Chargeback => {
-                // Take the transaction out to avoid multiple mutable borrows
-                if let Some(mut tx) = self.transactions.remove(&record.tx) {
+                // Keyed by (client, tx): a row whose client doesn't own this
+                // tx simply finds nothing here, so cross-client disputes are
+                // rejected the same way an unknown tx is.
+                if let Some(mut tx) = self.store.remove_tx(record.client, record.tx) {
                     let client_id = tx.client;
                     let amount = tx.amount;
 
                     // Borrow the account separately
                     let account = self.get_account(client_id);
 
-                    match record.tx_type {
+                    let result = match record.tx_type {
                         TransactionType::Dispute => {
-                            if !tx.disputed {
-                                account.hold(amount);
-                                tx.disputed = true;
+                            if tx.state == TxState::Processed {
+                                account.hold(&tx.currency, amount);
+                                tx.state = TxState::Disputed;
+                                Ok(())
+                            } else {
+                                // Only a freshly processed tx can enter dispute
+                                Err(LedgerError::AlreadyDisputed(client_id, record.tx))
                             }
                         }
                         TransactionType::Resolve => {
-                            if tx.disputed {
-                                account.release(amount);
-                                tx.disputed = false;
+                            if tx.state == TxState::Disputed {
+                                account.release(&tx.currency, amount);
+                                tx.state = TxState::Resolved;
+                                Ok(())
                             } else {
                                 // Resolve attempted on a transaction that isn't disputed
-                                warn!("resolve attempted for tx {} which is not under dispute", record.tx);
+                                Err(LedgerError::NotDisputed(client_id, record.tx))
                             }
                         }
                         TransactionType::Chargeback => {
-                            if tx.disputed {
-                                account.chargeback(amount);
-                                tx.disputed = false;
+                            if tx.state == TxState::Disputed {
+                                account.chargeback(&tx.currency, amount);
+                                tx.state = TxState::ChargedBack;
+                                Ok(())
                             } else {
                                 // Chargeback attempted on a transaction that isn't disputed
-                                warn!("chargeback attempted for tx {} which is not under dispute", record.tx);
+                                Err(LedgerError::NotDisputed(client_id, record.tx))
                             }
                         }
-                        _ => {}
-                    }
+                        _ => Ok(()),
+                    };
 
-                    // Put the transaction back into the map
-                    self.transactions.insert(record.tx, tx);
+                    // Put the transaction back into the store
+                    self.store.put_tx(record.client, record.tx, tx);
+                    result
                 } else {
                     // Transaction not found — log and ignore as per spec
                     debug!("ignoring {:?} for tx {}: transaction not found", record.tx_type, record.tx);
+                    Err(LedgerError::UnknownTx(record.client, record.tx))
                 }
             }
         }
     }
 
-    /// Output all accounts to stdout in CSV format
+    /// Output all accounts to stdout in CSV format, one row per (client, currency)
     pub fn output_accounts(&self) {
         let mut wtr = Writer::from_writer(std::io::stdout());
-        wtr.write_record(&["client", "available", "held", "total", "locked"])
+        wtr.write_record(&["client", "currency", "available", "held", "total", "locked"])
             .unwrap();
 
-        for account in self.accounts.values() {
-            wtr.serialize((
-                account.client,
-                account.available.round_dp(4),
-                account.held.round_dp(4),
-                account.total.round_dp(4),
-                account.locked,
-            ))
-            .unwrap();
+        for account in self.store.accounts() {
+            for (currency, balance) in &account.balances {
+                wtr.serialize((
+                    account.client,
+                    currency,
+                    balance.available.round_dp(4),
+                    balance.held.round_dp(4),
+                    balance.total.round_dp(4),
+                    account.locked,
+                ))
+                .unwrap();
+            }
         }
 
         wtr.flush().unwrap();
@@ -146,6 +289,7 @@ impl PaymentEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transactions::DEFAULT_CURRENCY;
     use rust_decimal::Decimal;
     use rust_decimal::prelude::FromPrimitive;
 
@@ -159,6 +303,7 @@ mod tests {
             client,
             tx,
             amount: Some(decimal(amount)),
+            currency: None,
         }
     }
 
@@ -168,6 +313,7 @@ mod tests {
             client,
             tx,
             amount: Some(decimal(amount)),
+            currency: None,
         }
     }
 
@@ -177,6 +323,7 @@ mod tests {
             client,
             tx,
             amount: None,
+            currency: None,
         }
     }
 
@@ -186,6 +333,7 @@ mod tests {
             client,
             tx,
             amount: None,
+            currency: None,
         }
     }
 
@@ -195,6 +343,7 @@ mod tests {
             client,
             tx,
             amount: None,
+            currency: None,
         }
     }
 
@@ -204,8 +353,8 @@ mod tests {
         engine.process_transaction(deposit(1, 1, 100.0));
         engine.process_transaction(withdrawal(1, 2, 50.0));
         let acc = engine.get_account(1);
-        assert_eq!(acc.available, decimal(50.0));
-        assert_eq!(acc.total, decimal(50.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(50.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].total, decimal(50.0));
         assert!(!acc.locked);
     }
 
@@ -215,8 +364,8 @@ mod tests {
         engine.process_transaction(deposit(2, 1, 100.0));
         engine.process_transaction(withdrawal(2, 2, 200.0)); // should fail
         let acc = engine.get_account(2);
-        assert_eq!(acc.available, decimal(100.0));
-        assert_eq!(acc.total, decimal(100.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(100.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].total, decimal(100.0));
     }
 
 
@@ -228,9 +377,9 @@ mod tests {
         engine.process_transaction(chargeback(2, 1));
 
         let acc = engine.get_account(2);
-        assert_eq!(acc.available, decimal(0.0));
-        assert_eq!(acc.held, decimal(0.0));
-        assert_eq!(acc.total, decimal(0.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(0.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].held, decimal(0.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].total, decimal(0.0));
         assert!(acc.locked);
     }
 
@@ -241,8 +390,8 @@ mod tests {
         engine.process_transaction(withdrawal(3, 2, 123.1234));
 
         let acc = engine.get_account(3);
-        assert_eq!(acc.available.round_dp(4), decimal(377.0000));
-        assert_eq!(acc.total.round_dp(4), decimal(377.0000));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available.round_dp(4), decimal(377.0000));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].total.round_dp(4), decimal(377.0000));
     }
 
     #[test]
@@ -254,11 +403,11 @@ mod tests {
         engine.process_transaction(withdrawal(2, 4, 50.0));
 
         // Immutable access for checks
-        let acc1 = engine.accounts.get(&1).unwrap();
-        let acc2 = engine.accounts.get(&2).unwrap();
+        let acc1 = engine.store.get_account(1).unwrap();
+        let acc2 = engine.store.get_account(2).unwrap();
 
-        assert_eq!(acc1.available, decimal(50.0));
-        assert_eq!(acc2.available, decimal(150.0));
+        assert_eq!(acc1.balances[DEFAULT_CURRENCY].available, decimal(50.0));
+        assert_eq!(acc2.balances[DEFAULT_CURRENCY].available, decimal(150.0));
     }
 
     #[test]
@@ -270,7 +419,7 @@ mod tests {
         engine.process_transaction(chargeback(1, 999));
 
         // Account should not exist yet
-        assert!(engine.accounts.get(&1).is_none());
+        assert!(engine.store.get_account(1).is_none());
     }
 
     #[test]
@@ -282,15 +431,15 @@ mod tests {
 
         // Withdrawal of 100 should fail and not be recorded
         engine.process_transaction(withdrawal(1, 2, 100.0));
-        assert!(engine.transactions.get(&2).is_none());
+        assert!(engine.store.get_tx(1, 2).is_none());
 
         // Disputing tx 2 should be ignored
         engine.process_transaction(dispute(1, 2));
 
         let acc = engine.get_account(1);
         // balances unchanged
-        assert_eq!(acc.available, decimal(50.0));
-        assert_eq!(acc.held, decimal(0.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(50.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].held, decimal(0.0));
     }
 
     #[test]
@@ -304,10 +453,10 @@ mod tests {
 
         let acc = engine.get_account(1);
         // Both deposits applied
-        assert_eq!(acc.available, decimal(150.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(150.0));
 
         // The recorded transaction should reflect the last inserted amount (50.0)
-        let tx = engine.transactions.get(&1).unwrap();
+        let tx = engine.store.get_tx(1, 1).unwrap();
         assert_eq!(tx.amount.round_dp(4), decimal(50.0));
     }
 
@@ -320,8 +469,8 @@ mod tests {
         engine.process_transaction(resolve(1, 1));
 
         let acc = engine.get_account(1);
-        assert_eq!(acc.available, decimal(100.0));
-        assert_eq!(acc.held, decimal(0.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(100.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].held, decimal(0.0));
     }
 
     #[test]
@@ -333,8 +482,8 @@ mod tests {
         engine.process_transaction(chargeback(1, 1));
 
         let acc = engine.get_account(1);
-        assert_eq!(acc.available, decimal(100.0));
-        assert_eq!(acc.held, decimal(0.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(100.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].held, decimal(0.0));
         assert!(!acc.locked);
     }
 
@@ -349,9 +498,207 @@ mod tests {
         engine.process_transaction(dispute(1, 2));
 
         let acc = engine.get_account(1);
-        assert_eq!(acc.available, decimal(0.0));
-        assert_eq!(acc.held, decimal(40.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(0.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].held, decimal(40.0));
         // total remains available + held = 40
-        assert_eq!(acc.total, decimal(40.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].total, decimal(40.0));
+    }
+
+    #[test]
+    fn test_resolved_tx_cannot_be_disputed_again() {
+        let mut engine = PaymentEngine::new();
+
+        engine.process_transaction(deposit(1, 1, 100.0));
+        engine.process_transaction(dispute(1, 1));
+        engine.process_transaction(resolve(1, 1));
+
+        // Re-disputing a resolved tx must be rejected: no funds get re-held
+        engine.process_transaction(dispute(1, 1));
+        engine.process_transaction(chargeback(1, 1));
+
+        let acc = engine.get_account(1);
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(100.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].held, decimal(0.0));
+        assert!(!acc.locked);
+    }
+
+    #[test]
+    fn test_chargedback_tx_cannot_be_resolved() {
+        let mut engine = PaymentEngine::new();
+
+        engine.process_transaction(deposit(1, 1, 100.0));
+        engine.process_transaction(dispute(1, 1));
+        engine.process_transaction(chargeback(1, 1));
+
+        // The account is locked and terminal; a stray resolve must be rejected
+        engine.process_transaction(resolve(1, 1));
+
+        let acc = engine.get_account(1);
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(0.0));
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].held, decimal(0.0));
+        assert!(acc.locked);
+    }
+
+    #[test]
+    fn test_try_process_transaction_reports_typed_errors() {
+        let mut engine = PaymentEngine::new();
+
+        engine.process_transaction(deposit(1, 1, 100.0));
+
+        assert_eq!(
+            engine.try_process_transaction(withdrawal(1, 2, 200.0)),
+            Err(LedgerError::NotEnoughFunds(1, 2))
+        );
+        assert_eq!(
+            engine.try_process_transaction(dispute(1, 999)),
+            Err(LedgerError::UnknownTx(1, 999))
+        );
+        assert_eq!(
+            engine.try_process_transaction(resolve(1, 1)),
+            Err(LedgerError::NotDisputed(1, 1))
+        );
+
+        assert_eq!(engine.try_process_transaction(dispute(1, 1)), Ok(()));
+        assert_eq!(
+            engine.try_process_transaction(dispute(1, 1)),
+            Err(LedgerError::AlreadyDisputed(1, 1))
+        );
+    }
+
+    #[test]
+    fn test_try_process_transaction_reports_frozen_account() {
+        let mut engine = PaymentEngine::new();
+
+        engine.process_transaction(deposit(1, 1, 100.0));
+        engine.process_transaction(dispute(1, 1));
+        engine.process_transaction(chargeback(1, 1));
+
+        assert_eq!(
+            engine.try_process_transaction(deposit(1, 2, 50.0)),
+            Err(LedgerError::FrozenAccount(1))
+        );
+    }
+
+    #[test]
+    fn test_cross_client_dispute_rejected() {
+        let mut engine = PaymentEngine::new();
+
+        // tx 1 belongs to client 1
+        engine.process_transaction(deposit(1, 1, 100.0));
+
+        // Client 2 tries to dispute client 1's transaction — must be rejected
+        assert_eq!(
+            engine.try_process_transaction(dispute(2, 1)),
+            Err(LedgerError::UnknownTx(2, 1))
+        );
+
+        let acc1 = engine.get_account(1);
+        assert_eq!(acc1.balances[DEFAULT_CURRENCY].available, decimal(100.0));
+        assert_eq!(acc1.balances[DEFAULT_CURRENCY].held, decimal(0.0));
+
+        // The rightful owner can still dispute it
+        assert_eq!(engine.try_process_transaction(dispute(1, 1)), Ok(()));
+        let acc1 = engine.get_account(1);
+        assert_eq!(acc1.balances[DEFAULT_CURRENCY].held, decimal(100.0));
+    }
+
+    #[test]
+    fn test_process_transaction_swallows_errors() {
+        let mut engine = PaymentEngine::new();
+
+        // process_transaction must preserve prior behavior: no panics, no
+        // propagated errors, rejected rows are simply no-ops.
+        engine.process_transaction(withdrawal(1, 1, 50.0));
+        let acc = engine.get_account(1);
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(0.0));
+    }
+
+    #[test]
+    fn test_multi_asset_balances_are_independent() {
+        let mut engine = PaymentEngine::new();
+
+        let btc_deposit = TransactionRecord {
+            tx_type: TransactionType::Deposit,
+            client: 1,
+            tx: 1,
+            amount: Some(decimal(2.0)),
+            currency: Some("BTC".to_string()),
+        };
+        let eth_deposit = TransactionRecord {
+            tx_type: TransactionType::Deposit,
+            client: 1,
+            tx: 2,
+            amount: Some(decimal(10.0)),
+            currency: Some("ETH".to_string()),
+        };
+        engine.process_transaction(btc_deposit);
+        engine.process_transaction(eth_deposit);
+
+        // Disputing the BTC deposit must not touch the ETH balance
+        engine.process_transaction(dispute(1, 1));
+
+        let acc = engine.get_account(1);
+        assert_eq!(acc.balances["BTC"].available, decimal(0.0));
+        assert_eq!(acc.balances["BTC"].held, decimal(2.0));
+        assert_eq!(acc.balances["ETH"].available, decimal(10.0));
+        assert_eq!(acc.balances["ETH"].held, decimal(0.0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_custom_store_implementation_is_usable() {
+        // A second TransactionStore impl should be a drop-in for MemStore.
+        struct VecStore {
+            accounts: Vec<ClientAccount>,
+            transactions: Vec<((u16, u32), Transaction)>,
+        }
+
+        impl TransactionStore for VecStore {
+            fn get_account(&self, client: u16) -> Option<&ClientAccount> {
+                self.accounts.iter().find(|a| a.client == client)
+            }
+
+            fn get_account_mut(&mut self, client: u16) -> &mut ClientAccount {
+                if let Some(idx) = self.accounts.iter().position(|a| a.client == client) {
+                    return &mut self.accounts[idx];
+                }
+                self.accounts.push(ClientAccount::new(client));
+                self.accounts.last_mut().unwrap()
+            }
+
+            fn put_account(&mut self, account: ClientAccount) {
+                if let Some(idx) = self.accounts.iter().position(|a| a.client == account.client) {
+                    self.accounts[idx] = account;
+                } else {
+                    self.accounts.push(account);
+                }
+            }
+
+            fn get_tx(&self, client: u16, tx: u32) -> Option<&Transaction> {
+                self.transactions.iter().find(|(k, _)| *k == (client, tx)).map(|(_, v)| v)
+            }
+
+            fn put_tx(&mut self, client: u16, tx: u32, transaction: Transaction) {
+                self.transactions.retain(|(k, _)| *k != (client, tx));
+                self.transactions.push(((client, tx), transaction));
+            }
+
+            fn remove_tx(&mut self, client: u16, tx: u32) -> Option<Transaction> {
+                let idx = self.transactions.iter().position(|(k, _)| *k == (client, tx))?;
+                Some(self.transactions.remove(idx).1)
+            }
+
+            fn accounts(&self) -> Box<dyn Iterator<Item = &ClientAccount> + '_> {
+                Box::new(self.accounts.iter())
+            }
+        }
+
+        let store = VecStore { accounts: Vec::new(), transactions: Vec::new() };
+        let mut engine = PaymentEngine::with_store(store);
+
+        engine.process_transaction(deposit(1, 1, 100.0));
+        engine.process_transaction(withdrawal(1, 2, 40.0));
+
+        let acc = engine.store.get_account(1).unwrap();
+        assert_eq!(acc.balances[DEFAULT_CURRENCY].available, decimal(60.0));
+    }
+}
@@ -3,7 +3,7 @@ pub mod engine;
 pub mod accounts;
 pub mod transactions;
 
-pub use orchestrator::run;
-pub use engine::PaymentEngine;
-pub use transactions::TransactionRecord;
+pub use orchestrator::{run, run_from_reader, run_parallel};
+pub use engine::{PaymentEngine, LedgerError, TransactionStore, MemStore};
+pub use transactions::{TransactionRecord, Currency, DEFAULT_CURRENCY};
 pub use accounts::client_account::ClientAccount;
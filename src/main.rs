@@ -6,7 +6,7 @@ mod accounts;
 use::std::env;
 use::std::process;
 
-use orchestrator::run;
+use orchestrator::{run, run_from_reader};
 use env_logger;
 use log::info;
 
@@ -14,7 +14,7 @@ fn main() {
     // Collect command-line arguments - expecting exactly one argument for the CSV file path
     let args: Vec<String> = env::args().collect();
     if args.len() != 2 {
-        eprintln!("Usage: {} <transactions.csv>", args[0]);
+        eprintln!("Usage: {} <transactions.csv | ->", args[0]);
         process::exit(1);
     }
     // Call the run function with the provided filename
@@ -22,9 +22,16 @@ fn main() {
     // Initialize logger (respect RUST_LOG env var if set)
     env_logger::init();
 
-    info!("starting payments engine with file: {}", filename);
+    // "-" means read transactions from stdin instead of a named file
+    let result = if filename == "-" {
+        info!("starting payments engine reading from stdin");
+        run_from_reader(std::io::stdin())
+    } else {
+        info!("starting payments engine with file: {}", filename);
+        run(filename)
+    };
 
-    if let Err(e) = run(filename) {
+    if let Err(e) = result {
         eprintln!("Error: {}", e);
         process::exit(1);
     }
@@ -1,22 +1,125 @@
 use std::fs::File;
 use std::error::Error;
+use std::io::Read;
+use std::sync::mpsc;
+use std::thread;
 use csv::ReaderBuilder;
+use log::warn;
 
-use crate::engine::PaymentEngine;
+use crate::engine::{PaymentEngine, TransactionStore};
 use crate::transactions::TransactionRecord;
 
 pub fn run(filename: &str) -> Result<(), Box<dyn Error>> {
     let file = File::open(filename)?;
-    let mut rdr: csv::Reader<File> = ReaderBuilder::new().trim(csv::Trim::All).from_reader(file);
+    run_from_reader(file)
+}
+
+/// Run the engine over any `Read`er of transaction CSV rows.
+///
+/// Records are deserialized and applied one at a time rather than buffered
+/// up front, so memory stays flat regardless of input size — this lets the
+/// engine be fed from stdin, a socket, or an in-memory buffer in tests just
+/// as easily as from a file.
+pub fn run_from_reader<R: Read>(reader: R) -> Result<(), Box<dyn Error>> {
+    let mut rdr: csv::Reader<R> = ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
 
     let mut engine = PaymentEngine::new();
 
     for result in rdr.deserialize() {
         let record: TransactionRecord = result?;
-        engine.process_transaction(record);
+        if let Err(err) = engine.try_process_transaction(record) {
+            // Per-row errors are reported, not fatal: one bad row shouldn't
+            // abort processing of the rest of the stream.
+            warn!("{}", err);
+        }
     }
 
     engine.output_accounts();
 
     Ok(())
+}
+
+/// Run the engine over a `Read`er of transaction CSV rows, sharding work
+/// across `num_threads` per-client worker threads.
+///
+/// Every transaction's effect is confined to its own client, and disputes
+/// always reference the same client as the original transaction, so routing
+/// every row for a given client to the same shard (`client % num_threads`)
+/// preserves correctness while processing shards in parallel. Rows are fed
+/// into each shard's channel in input order, so per-client ordering is
+/// unaffected even though shards run concurrently.
+pub fn run_parallel<R: Read>(reader: R, num_threads: usize) -> Result<(), Box<dyn Error>> {
+    let merged = process_parallel(reader, num_threads)?;
+    merged.output_accounts();
+    Ok(())
+}
+
+/// Shard-and-merge logic behind [`run_parallel`], split out so the merged
+/// engine can be inspected directly in tests instead of only via stdout.
+fn process_parallel<R: Read>(reader: R, num_threads: usize) -> Result<PaymentEngine, Box<dyn Error>> {
+    let num_threads = num_threads.max(1);
+
+    let mut senders = Vec::with_capacity(num_threads);
+    let mut handles = Vec::with_capacity(num_threads);
+    for _ in 0..num_threads {
+        let (sender, receiver) = mpsc::channel::<TransactionRecord>();
+        let handle = thread::spawn(move || {
+            let mut engine = PaymentEngine::new();
+            for record in receiver {
+                if let Err(err) = engine.try_process_transaction(record) {
+                    warn!("{}", err);
+                }
+            }
+            engine
+        });
+        senders.push(sender);
+        handles.push(handle);
+    }
+
+    let mut rdr: csv::Reader<R> = ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    for result in rdr.deserialize() {
+        let record: TransactionRecord = result?;
+        let shard = record.client as usize % num_threads;
+        // A send error means that shard's thread already exited, which only
+        // happens if it panicked; propagate rather than silently dropping rows.
+        senders[shard].send(record)?;
+    }
+    // Dropping the senders closes each shard's channel, letting its thread
+    // finish once its queued rows are drained.
+    drop(senders);
+
+    let mut merged = PaymentEngine::new();
+    for handle in handles {
+        let shard_engine = handle.join().map_err(|_| {
+            std::io::Error::other("a parallel processing shard thread panicked")
+        })?;
+        for (_, account) in shard_engine.store.into_accounts() {
+            merged.store.put_account(account);
+        }
+    }
+
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_process_parallel_merges_shards_by_client() {
+        let csv = "type,client,tx,amount\n\
+                   deposit,1,1,100.0\n\
+                   deposit,2,2,50.0\n\
+                   withdrawal,1,3,40.0\n\
+                   dispute,2,2,\n";
+
+        let merged = process_parallel(Cursor::new(csv), 4).unwrap();
+
+        let acc1 = merged.store.get_account(1).unwrap();
+        assert_eq!(acc1.balances[crate::transactions::DEFAULT_CURRENCY].available, rust_decimal::Decimal::new(600, 1));
+
+        let acc2 = merged.store.get_account(2).unwrap();
+        assert_eq!(acc2.balances[crate::transactions::DEFAULT_CURRENCY].held, rust_decimal::Decimal::new(500, 1));
+    }
 }
\ No newline at end of file
@@ -1,6 +1,13 @@
 use rust_decimal::Decimal;
 use serde::Deserialize;
 
+/// Asset a balance or transaction is denominated in.
+pub type Currency = String;
+
+/// Asset assumed for rows that omit the `currency` column, so existing
+/// single-asset input streams keep working unchanged.
+pub const DEFAULT_CURRENCY: &str = "default";
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TransactionType {
@@ -18,11 +25,35 @@ pub struct TransactionRecord {
     pub client: u16,
     pub tx: u32,
     pub amount: Option<Decimal>,
+    #[serde(default)]
+    pub currency: Option<Currency>,
+}
+
+impl TransactionRecord {
+    /// The asset this row applies to, falling back to [`DEFAULT_CURRENCY`]
+    /// when the `currency` column was absent from the input.
+    pub fn currency(&self) -> &str {
+        self.currency.as_deref().unwrap_or(DEFAULT_CURRENCY)
+    }
+}
+
+/// Lifecycle of a recorded deposit/withdrawal.
+///
+/// The only legal transitions are `Processed -> Disputed`, `Disputed ->
+/// Resolved`, and `Disputed -> ChargedBack`. `Resolved` and `ChargedBack`
+/// are terminal: once reached, a transaction can never be disputed again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxState {
+    Processed,
+    Disputed,
+    Resolved,
+    ChargedBack,
 }
 
 #[derive(Debug, Clone)]
 pub struct Transaction {
     pub client: u16,
     pub amount: Decimal,
-    pub disputed: bool,
+    pub currency: Currency,
+    pub state: TxState,
 }